@@ -12,7 +12,7 @@ pub mod lexer;
 pub use lexer::Lexer;
 
 mod parser;
-pub use parser::Parser;
+pub use parser::{Parser, Restrictions};
 
 mod session;
 pub use session::ParseSess;
@@ -1,11 +1,15 @@
-use crate::{PResult, Parser};
+use crate::{PResult, Parser, Restrictions};
 use sulk_ast::{ast::*, token::*};
-use sulk_interface::kw;
+use sulk_interface::{diagnostics::Applicability, kw, Span};
 
 impl<'a> Parser<'a> {
     /// Parses an expression.
     pub fn parse_expr(&mut self) -> PResult<'a, Box<Expr>> {
-        let expr = self.parse_binary_expr(4)?;
+        self.with_nesting_guard(Self::parse_expr_inner)
+    }
+
+    fn parse_expr_inner(&mut self) -> PResult<'a, Box<Expr>> {
+        let expr = self.parse_binary_expr(1)?;
         if self.eat(&TokenKind::Question) {
             let then = self.parse_expr()?;
             self.expect(&TokenKind::Colon)?;
@@ -16,6 +20,20 @@ impl<'a> Parser<'a> {
             let kind = if let Some(binop_eq) = self.token.as_binop_eq() {
                 Some(binop_eq)
             } else if self.token.kind == TokenKind::Eq {
+                // A bare `=` in a genuine boolean condition is almost always a typo for `==`.
+                if self.restrictions().contains(Restrictions::IS_CONDITION) {
+                    let span = self.token.span;
+                    self.dcx()
+                        .err("assignment used as a condition")
+                        .span(span)
+                        .span_suggestion(
+                            span,
+                            "use `==` to compare, or wrap in parentheses to assign",
+                            "==",
+                            Applicability::MaybeIncorrect,
+                        )
+                        .emit();
+                }
                 None
             } else {
                 return Ok(expr);
@@ -27,47 +45,76 @@ impl<'a> Parser<'a> {
         }
     }
 
-    /// Parses a binary expression.
-    fn parse_binary_expr(&mut self, min_precedence: usize) -> PResult<'a, Box<Expr>> {
+    /// Parses a binary expression, via Pratt parsing: a binary operator is only consumed while
+    /// its left binding power is at least `min_bp`, and its right-hand side is parsed with a
+    /// minimum of its right binding power, so that the tree nests according to each operator's
+    /// precedence and associativity. Assignment, ternary, and comma are not binary operators in
+    /// this table; they are handled by [`Parser::parse_expr`] on top of the result.
+    fn parse_binary_expr(&mut self, min_bp: usize) -> PResult<'a, Box<Expr>> {
+        self.with_nesting_guard(|this| this.parse_binary_expr_inner(min_bp))
+    }
+
+    fn parse_binary_expr_inner(&mut self, min_bp: usize) -> PResult<'a, Box<Expr>> {
         let mut expr = self.parse_unary_expr()?;
-        let mut precedence = token_precedence(&self.token);
-        while precedence >= min_precedence {
-            while token_precedence(&self.token) == precedence {
-                // Parse a**b**c as a**(b**c)
-                let next_precedence = if self.token.kind == TokenKind::BinOp(BinOpToken::Star) {
-                    precedence + 1
-                } else {
-                    precedence
-                };
+        // Tracks the span of the most recent comparison operator seen at this level, so that a
+        // second one (`a < b < c`) can be flagged instead of silently building a left-nested
+        // tree that evaluates `(a < b) < c`.
+        let mut chained_cmp: Option<Span> = None;
+        while let Some((left_bp, right_bp)) = binding_power(&self.token) {
+            if left_bp < min_bp {
+                break;
+            }
 
-                let token = self.token.clone();
-                self.bump(); // binop token
+            let token = self.token.clone();
+            self.bump(); // binop token
 
-                let rhs = self.parse_binary_expr(next_precedence)?;
+            if is_comparison(&token.kind) {
+                if let Some(prev_span) = chained_cmp {
+                    let msg = "comparison operators cannot be chained";
+                    self.dcx()
+                        .err(msg)
+                        .span(token.span)
+                        .span_label(prev_span, "first comparison here")
+                        .help("split into separate comparisons joined by `&&`")
+                        .emit();
+                }
+                chained_cmp = Some(token.span);
+            } else {
+                chained_cmp = None;
+            }
 
-                let span = expr.span.to(self.prev_token.span);
+            let rhs = self.parse_binary_expr(right_bp)?;
 
-                let kind = if let Some(binop) = token.as_binop() {
-                    ExprKind::Binary(expr, binop, rhs)
-                } else if let Some(binop_eq) = token.as_binop_eq() {
-                    ExprKind::Assign(expr, Some(binop_eq), rhs)
-                } else if token.kind == TokenKind::Eq {
-                    ExprKind::Assign(expr, None, rhs)
-                } else {
-                    let msg = format!("unkown binop token: {token:?}");
-                    self.dcx().bug(msg).span(span).emit();
-                };
-                expr = Box::new(Expr { span, kind });
-            }
-            precedence -= 1;
+            let span = expr.span.to(self.prev_token.span);
+            let kind = if let Some(binop) = token.as_binop() {
+                ExprKind::Binary(expr, binop, rhs)
+            } else {
+                let msg = format!("unkown binop token: {token:?}");
+                self.dcx().bug(msg).span(span).emit();
+            };
+            expr = Box::new(Expr { span, kind });
         }
         Ok(expr)
     }
 
     /// Parses a unary expression.
     fn parse_unary_expr(&mut self) -> PResult<'a, Box<Expr>> {
+        self.with_nesting_guard(Self::parse_unary_expr_inner)
+    }
+
+    fn parse_unary_expr_inner(&mut self) -> PResult<'a, Box<Expr>> {
         if self.eat(&TokenKind::BinOp(BinOpToken::Plus)) {
-            self.dcx().err("unary plus is not supported").emit();
+            let span = self.prev_token.span;
+            self.dcx()
+                .err("unary plus is not supported")
+                .span(span)
+                .span_suggestion(
+                    span,
+                    "remove the unary `+`",
+                    "",
+                    Applicability::MachineApplicable,
+                )
+                .emit();
         }
 
         let lo = self.token.span;
@@ -97,6 +144,10 @@ impl<'a> Parser<'a> {
 
     /// Parses a primary left-hand-side expression.
     fn parse_lhs_expr(&mut self) -> PResult<'a, Box<Expr>> {
+        self.with_nesting_guard(Self::parse_lhs_expr_inner)
+    }
+
+    fn parse_lhs_expr_inner(&mut self) -> PResult<'a, Box<Expr>> {
         let lo = self.token.span;
         let mut expr = if self.eat_keyword(kw::New) {
             self.parse_type().map(|ty| {
@@ -118,6 +169,7 @@ impl<'a> Parser<'a> {
                 let args = self.parse_call_args()?;
                 ExprKind::Call(expr, args)
             } else if self.eat(&TokenKind::OpenDelim(Delimiter::Bracket)) {
+                self.open_delim(Delimiter::Bracket, self.prev_token.span);
                 // expr[], expr[start?], expr[start?:end?]
                 let kind = if self.check(&TokenKind::CloseDelim(Delimiter::Bracket)) {
                     let start =
@@ -138,11 +190,13 @@ impl<'a> Parser<'a> {
                     // expr[]
                     IndexKind::Index(None)
                 };
-                self.expect(&TokenKind::CloseDelim(Delimiter::Bracket))?;
+                self.expect_close_delim(Delimiter::Bracket)?;
                 ExprKind::Index(expr, kind)
             } else if self.check(&TokenKind::OpenDelim(Delimiter::Brace)) {
-                // This may be `try` statement block.
-                if !self.look_ahead(1).is_ident() || self.look_ahead(2).kind != TokenKind::Colon {
+                // A control-flow header (e.g. `for (...; cond; ...)`) sets `NO_CALL_OPTIONS`
+                // while parsing its expression, so that the `{` starting its body is never
+                // mistaken for call options.
+                if self.restrictions().contains(Restrictions::NO_CALL_OPTIONS) {
                     break;
                 }
 
@@ -160,14 +214,18 @@ impl<'a> Parser<'a> {
 
     /// Parses a primary expression.
     fn parse_primary_expr(&mut self) -> PResult<'a, Box<Expr>> {
+        self.with_nesting_guard(Self::parse_primary_expr_inner)
+    }
+
+    fn parse_primary_expr_inner(&mut self) -> PResult<'a, Box<Expr>> {
         let lo = self.token.span;
         let kind = if self.token.is_lit() || self.token.is_bool_lit() {
             let (lit, sub) = self.parse_lit_with_subdenomination()?;
             ExprKind::Lit(lit, sub)
         } else if self.eat_keyword(kw::Type) {
-            self.expect(&TokenKind::OpenDelim(Delimiter::Parenthesis))?;
+            self.expect_open_delim(Delimiter::Parenthesis)?;
             let ty = self.parse_type()?;
-            self.expect(&TokenKind::CloseDelim(Delimiter::Parenthesis))?;
+            self.expect_close_delim(Delimiter::Parenthesis)?;
             ExprKind::TypeCall(ty)
         } else if self.check_elementary_type() {
             let ty = self.parse_type()?;
@@ -181,13 +239,22 @@ impl<'a> Parser<'a> {
             // Array or tuple expression.
             let TokenKind::OpenDelim(close_delim) = self.token.kind else { unreachable!() };
             self.bump(); // open delim
+            self.open_delim(close_delim, self.prev_token.span);
             let is_array = close_delim == Delimiter::Bracket;
-            let list = self.parse_seq_optional_items(close_delim, |this| this.parse_expr())?;
+            // The enclosing restrictions don't apply inside the delimiters.
+            let list = self.with_res(Restrictions::empty(), |this| {
+                this.parse_comma_separated_optional(close_delim, Self::parse_expr)
+            });
+            self.expect_close_delim(close_delim)?;
             if is_array {
-                if !list.iter().all(Option::is_some) {
+                if let Some(missing) = list.iter().position(Option::is_none) {
                     let msg = "array expression components cannot be empty";
                     let span = lo.to(self.prev_token.span);
-                    return Err(self.dcx().err(msg).span(span));
+                    return Err(self
+                        .dcx()
+                        .err(msg)
+                        .span(span)
+                        .span_help(span, format!("element {missing} is missing a value")));
                 }
                 // SAFETY: All elements are checked to be `Some` above.
                 ExprKind::Array(unsafe { vec_option_box_unwrap_unchecked(list) })
@@ -195,7 +262,10 @@ impl<'a> Parser<'a> {
                 ExprKind::Tuple(list)
             }
         } else {
-            return self.unexpected();
+            // Don't bail out of the whole parse on a single malformed expression: emit the
+            // diagnostic, skip to a synchronizing token, and hand back an `Err` placeholder so
+            // the caller can keep going.
+            return Ok(self.recover_expr(lo));
         };
         let span = lo.to(self.prev_token.span);
         Ok(Box::new(Expr { span, kind }))
@@ -204,9 +274,9 @@ impl<'a> Parser<'a> {
     /// Parses a list of function call arguments.
     pub(super) fn parse_call_args(&mut self) -> PResult<'a, CallArgs> {
         if self.look_ahead(1).kind == TokenKind::OpenDelim(Delimiter::Brace) {
-            self.expect(&TokenKind::OpenDelim(Delimiter::Parenthesis))?;
+            self.expect_open_delim(Delimiter::Parenthesis)?;
             let args = self.parse_named_args().map(CallArgs::Named)?;
-            self.expect(&TokenKind::CloseDelim(Delimiter::Parenthesis))?;
+            self.expect_close_delim(Delimiter::Parenthesis)?;
             Ok(args)
         } else {
             self.parse_unnamed_args().map(CallArgs::Unnamed)
@@ -215,7 +285,10 @@ impl<'a> Parser<'a> {
 
     /// Parses a list of named arguments: `{a: b, c: d, ...}`
     fn parse_named_args(&mut self) -> PResult<'a, NamedArgList> {
-        self.parse_delim_comma_seq(Delimiter::Brace, Self::parse_named_arg).map(|(x, _)| x)
+        self.expect_open_delim(Delimiter::Brace)?;
+        let args = self.parse_comma_separated(Delimiter::Brace, Self::parse_named_arg);
+        self.expect_close_delim(Delimiter::Brace)?;
+        Ok(args)
     }
 
     /// Parses a single named argument: `a: b`.
@@ -228,39 +301,45 @@ impl<'a> Parser<'a> {
 
     /// Parses a list of expressions: `(a, b, c, ...)`.
     fn parse_unnamed_args(&mut self) -> PResult<'a, Vec<Box<Expr>>> {
-        self.parse_paren_comma_seq(Self::parse_expr).map(|(x, _)| x)
+        self.expect_open_delim(Delimiter::Parenthesis)?;
+        let args = self.parse_comma_separated(Delimiter::Parenthesis, Self::parse_expr);
+        self.expect_close_delim(Delimiter::Parenthesis)?;
+        Ok(args)
     }
 }
 
-fn token_precedence(t: &Token) -> usize {
+/// Returns `true` if `kind` is one of the (non-associative) comparison operators.
+fn is_comparison(kind: &TokenKind) -> bool {
+    use TokenKind::*;
+    matches!(kind, EqEq | Ne | Lt | Gt | Le | Ge)
+}
+
+/// Returns the `(left, right)` binding power of a binary operator token, or `None` if `t` does
+/// not start one. Assignment, ternary, and comma are not part of this table; `parse_expr` sits
+/// on top of the Pratt result and handles those.
+///
+/// A left-associative operator uses `(n, n + 1)`: the right-hand side is parsed with a minimum
+/// binding power one higher than its own, so a same-precedence operator to the right stops the
+/// recursion and the tree nests to the left (`a - b - c` => `(a - b) - c`). The right-associative
+/// `**` instead uses `(n, n - 1)`, with `n` set above every multiplicative operator, so that
+/// `a ** b ** c` nests to the right and `2 * 3 ** 2` parses as `2 * (3 ** 2)`.
+fn binding_power(t: &Token) -> Option<(usize, usize)> {
     use BinOpToken::*;
     use TokenKind::*;
-    match t.kind {
-        Question => 3,
-        BinOpEq(_) => 2,
-        Comma => 1,
-        OrOr => 4,
-        AndAnd => 5,
-        BinOp(Or) => 8,
-        BinOp(Caret) => 9,
-        BinOp(Shl) => 11,
-        BinOp(Sar) => 11,
-        BinOp(Shr) => 11,
-        BinOp(Plus) => 12,
-        BinOp(Minus) => 12,
-        BinOp(Star) => 13,
-        BinOp(Slash) => 13,
-        BinOp(Percent) => 13,
-        StarStar => 4,
-        Eq => 6,
-        Ne => 6,
-        Lt => 7,
-        Gt => 7,
-        Le => 7,
-        Ge => 7,
-        Walrus => 2,
-        _ => 0,
-    }
+    Some(match t.kind {
+        OrOr => (1, 2),
+        AndAnd => (3, 4),
+        // `Eq` (bare `=`) is deliberately excluded: it is assignment, not equality, and is
+        // handled by `parse_expr` on top of this table. Only `EqEq` (`==`) is a comparison.
+        EqEq | Ne | Lt | Gt | Le | Ge => (5, 6),
+        BinOp(Or) => (7, 8),
+        BinOp(Caret) => (9, 10),
+        BinOp(Shl) | BinOp(Sar) | BinOp(Shr) => (11, 12),
+        BinOp(Plus) | BinOp(Minus) => (13, 14),
+        BinOp(Star) | BinOp(Slash) | BinOp(Percent) => (15, 16),
+        StarStar => (18, 17),
+        _ => return None,
+    })
 }
 
 /// Converts a vector of `Option<Box<T>>` into a vector of `Box<T>`.
@@ -277,3 +356,102 @@ unsafe fn vec_option_box_unwrap_unchecked<T>(vec: Vec<Option<Box<T>>>) -> Vec<Bo
     // SAFETY: Caller must ensure that all elements are `Some`.
     unsafe { std::mem::transmute(vec) }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Lexer, PResult, ParseSess, Parser};
+    use sulk_ast::ast::{Expr, ExprKind};
+    use sulk_interface::diagnostics::DiagCtxt;
+
+    /// Parses `src` as a standalone expression and hands the result, together with the session
+    /// it was parsed under, to `check`.
+    fn check_expr(src: &str, check: impl FnOnce(PResult<'_, Box<Expr>>, &ParseSess)) {
+        let sess = ParseSess::new(DiagCtxt::default());
+        let tokens = Lexer::new(&sess, src).tokenize();
+        let expr = Parser::new(&sess, tokens).parse_expr();
+        check(expr, &sess);
+    }
+
+    #[test]
+    fn exponent_binds_tighter_than_multiplication() {
+        // `2 * 3 ** 2` must parse as `2 * (3 ** 2)`, not `(2 * 3) ** 2`.
+        check_expr("2 * 3 ** 2", |expr, _sess| {
+            let expr = expr.unwrap();
+            let ExprKind::Binary(lhs, _, rhs) = &expr.kind else {
+                panic!("expected a binary expr")
+            };
+            assert!(matches!(lhs.kind, ExprKind::Lit(..)), "lhs should be the literal `2`");
+            assert!(matches!(rhs.kind, ExprKind::Binary(..)), "rhs should be the nested `3 ** 2`");
+        });
+    }
+
+    #[test]
+    fn exponent_is_right_associative() {
+        // `a ** b ** c` must parse as `a ** (b ** c)`, not `(a ** b) ** c`.
+        check_expr("a ** b ** c", |expr, _sess| {
+            let expr = expr.unwrap();
+            let ExprKind::Binary(lhs, _, rhs) = &expr.kind else {
+                panic!("expected a binary expr")
+            };
+            assert!(matches!(lhs.kind, ExprKind::Ident(_)), "lhs should be the single ident `a`");
+            assert!(matches!(rhs.kind, ExprKind::Binary(..)), "rhs should be the nested `b ** c`");
+        });
+    }
+
+    #[test]
+    fn chained_comparison_is_flagged_but_still_parses() {
+        check_expr("a < b < c", |expr, sess| {
+            assert!(matches!(expr.unwrap().kind, ExprKind::Binary(..)));
+            assert!(sess.dcx.has_errors().is_some(), "chained comparison should be diagnosed");
+        });
+    }
+
+    #[test]
+    fn comparison_across_parens_is_not_chained() {
+        // `(a < b) < c` re-parenthesizes the first comparison, so this is not the chained-
+        // comparison footgun and must not be flagged.
+        check_expr("(a < b) < c", |expr, sess| {
+            expr.unwrap();
+            assert!(sess.dcx.has_errors().is_none());
+        });
+    }
+
+    #[test]
+    fn deeply_nested_parens_hit_the_recursion_limit_instead_of_overflowing() {
+        // The fatal nesting error is raised deep inside the parenthesized sub-expression, but it
+        // gets absorbed by the enclosing tuple/array recovery (`parse_comma_separated_optional`)
+        // rather than propagated all the way out, so the top-level parse still succeeds; only
+        // the session's error count reflects that the limit was hit.
+        let src = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        check_expr(&src, |expr, sess| {
+            expr.unwrap();
+            assert!(sess.dcx.has_errors().is_some());
+        });
+    }
+
+    #[test]
+    fn missing_closing_paren_in_call_args_recovers_to_a_partial_ast() {
+        check_expr("foo(a, b", |expr, sess| {
+            assert!(matches!(expr.unwrap().kind, ExprKind::Call(..)));
+            assert!(sess.dcx.has_errors().is_some());
+        });
+    }
+
+    #[test]
+    fn malformed_array_element_recovers_instead_of_aborting() {
+        // The first element (a ternary missing its `:`) is malformed, but the parser should
+        // still return an `Array` with an `Err` placeholder in its place instead of bailing out
+        // of the whole expression. Note: an empty slot (`[, 2, 3]`) is a different, hard-error
+        // case (array components cannot be empty) and doesn't exercise this recovery path.
+        check_expr("[a ? b, 2, 3]", |expr, sess| {
+            let expr = expr.unwrap();
+            let ExprKind::Array(elems) = &expr.kind else { panic!("expected an array expr") };
+            assert_eq!(elems.len(), 3);
+            assert!(
+                matches!(elems[0].kind, ExprKind::Err(_)),
+                "first element should recover to `Err`"
+            );
+            assert!(sess.dcx.has_errors().is_some());
+        });
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{PResult, ParseSess};
+use sulk_ast::token::{Delimiter, Token};
+use sulk_interface::Span;
+
+mod expr;
+mod recovery;
+mod restrictions;
+mod stmt;
+
+pub use restrictions::Restrictions;
+
+/// A Solidity parser.
+pub struct Parser<'a> {
+    /// The parser session.
+    pub sess: &'a ParseSess,
+
+    /// The current token.
+    pub token: Token,
+    /// The previous token.
+    pub prev_token: Token,
+
+    /// Restrictions applied to the current sub-parse. See [`Restrictions`].
+    restrictions: Restrictions,
+
+    /// Current expression/type nesting depth, checked against `self.sess.recursion_limit()` by
+    /// [`Parser::with_nesting_guard`].
+    nesting_depth: usize,
+
+    /// Stack of currently-open delimiters and the span of their opening token.
+    ///
+    /// Pushed by [`Parser::expect_open_delim`] and popped by [`Parser::expect_close_delim`], so
+    /// that a missing or mismatched closer can be reported against both the unclosed opener and
+    /// the mismatch site instead of a single confusing "expected X, found Y" error.
+    open_delims: Vec<(Delimiter, Span)>,
+}
+
+impl<'a> Parser<'a> {
+    /// Runs `f` with `restrictions` applied for its duration, restoring the previous
+    /// restrictions once `f` returns, regardless of the result.
+    ///
+    /// Used by statement parsers to set [`Restrictions::NO_CALL_OPTIONS`] while parsing a
+    /// control-flow header expression (e.g. the condition of a `for`/`while`/`if`), so that the
+    /// header's trailing `{` is never misparsed as call options.
+    pub(crate) fn with_res<T>(
+        &mut self,
+        restrictions: Restrictions,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let old = std::mem::replace(&mut self.restrictions, restrictions);
+        let result = f(self);
+        self.restrictions = old;
+        result
+    }
+
+    /// Returns the restrictions currently applied to this parser.
+    pub(crate) fn restrictions(&self) -> Restrictions {
+        self.restrictions
+    }
+
+    /// Runs `f` one level deeper in the expression/type nesting count, bailing out with a fatal
+    /// "expression nesting too deep" diagnostic instead of recursing further once
+    /// `self.sess.recursion_limit()` is exceeded.
+    ///
+    /// Wraps every mutually-recursive expression entry point (`parse_expr`, `parse_binary_expr`,
+    /// `parse_unary_expr`, `parse_lhs_expr`, `parse_primary_expr`) so that pathological input
+    /// such as thousands of nested parentheses fails cleanly with a diagnostic instead of
+    /// overflowing the stack.
+    pub(crate) fn with_nesting_guard<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> PResult<'a, T>,
+    ) -> PResult<'a, T> {
+        self.nesting_depth += 1;
+        let result = if self.nesting_depth > self.sess.recursion_limit() {
+            let msg = "expression nesting too deep";
+            Err(self.dcx().fatal(msg).span(self.token.span))
+        } else {
+            f(self)
+        };
+        self.nesting_depth -= 1;
+        result
+    }
+}
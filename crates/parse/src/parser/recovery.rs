@@ -0,0 +1,192 @@
+use super::Parser;
+use crate::PResult;
+use sulk_ast::{
+    ast::{Expr, ExprKind, Stmt, StmtKind},
+    token::{Delimiter, Token, TokenKind},
+};
+use sulk_interface::{kw, Span};
+
+impl<'a> Parser<'a> {
+    /// Parses a comma-separated sequence of `T`, stopping before the closing `delim` (which the
+    /// caller is expected to have opened with [`Parser::expect_open_delim`] and will close with
+    /// [`Parser::expect_close_delim`]).
+    ///
+    /// A malformed item doesn't abort the whole sequence: its diagnostic is emitted, the parser
+    /// skips to the next comma or closing delimiter, and the item is dropped from the result, so
+    /// a single bad call argument still leaves the caller with a (shorter) partial argument list
+    /// instead of no parse at all.
+    pub(super) fn parse_comma_separated<T>(
+        &mut self,
+        delim: Delimiter,
+        mut f: impl FnMut(&mut Self) -> PResult<'a, T>,
+    ) -> Vec<T> {
+        let mut items = Vec::new();
+        while !self.check(&TokenKind::CloseDelim(delim)) && !self.check(&TokenKind::Eof) {
+            match f(self) {
+                Ok(item) => items.push(item),
+                Err(err) => {
+                    err.emit();
+                    self.recover_to_sync();
+                }
+            }
+            if !self.eat(&TokenKind::Comma) {
+                break;
+            }
+        }
+        items
+    }
+
+    /// Like [`Parser::parse_comma_separated`], but each comma-separated slot may be empty
+    /// (`[a, , c]`), and a malformed item becomes an `ExprKind::Err` placeholder instead of being
+    /// dropped, so the result still has one entry per slot.
+    pub(super) fn parse_comma_separated_optional(
+        &mut self,
+        delim: Delimiter,
+        mut f: impl FnMut(&mut Self) -> PResult<'a, Box<Expr>>,
+    ) -> Vec<Option<Box<Expr>>> {
+        let mut items = Vec::new();
+        loop {
+            if self.check(&TokenKind::CloseDelim(delim)) || self.check(&TokenKind::Eof) {
+                break;
+            }
+            if self.check(&TokenKind::Comma) {
+                items.push(None);
+            } else {
+                let lo = self.token.span;
+                match f(self) {
+                    Ok(expr) => items.push(Some(expr)),
+                    Err(err) => {
+                        let guar = err.emit();
+                        self.recover_to_sync();
+                        let span = lo.to(self.prev_token.span);
+                        items.push(Some(Box::new(Expr { span, kind: ExprKind::Err(guar) })));
+                    }
+                }
+            }
+            if !self.eat(&TokenKind::Comma) {
+                break;
+            }
+        }
+        items
+    }
+}
+
+/// Tokens that a recovery skip stops before, so that a botched expression doesn't eat the rest
+/// of the enclosing statement, argument list, or file.
+fn is_sync_token(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Semi
+            | TokenKind::Comma
+            | TokenKind::CloseDelim(Delimiter::Parenthesis)
+            | TokenKind::CloseDelim(Delimiter::Brace)
+            | TokenKind::CloseDelim(Delimiter::Bracket)
+            | TokenKind::Eof
+    )
+}
+
+/// Keywords that start a new statement, so that statement-level recovery (see
+/// [`Parser::recover_to_stmt_sync`]) treats them as synchronizing too, even though they aren't
+/// covered by [`is_sync_token`]: a malformed statement should never be allowed to swallow the
+/// `if`/`while`/... that starts the next one.
+fn is_stmt_start(token: &Token) -> bool {
+    [kw::If, kw::While, kw::Do, kw::For, kw::Try, kw::Return, kw::Break, kw::Continue, kw::Emit]
+        .into_iter()
+        .any(|kw| token.is_keyword(kw))
+}
+
+impl<'a> Parser<'a> {
+    /// Skips tokens until a synchronizing token (`;`, `,`, a closing delimiter, or EOF) is
+    /// reached, without consuming it.
+    ///
+    /// Called after a recovery diagnostic has been emitted, so that the parser can resume at the
+    /// next sensible boundary instead of aborting the whole parse.
+    pub(super) fn recover_to_sync(&mut self) {
+        while !is_sync_token(&self.token.kind) {
+            self.bump();
+        }
+    }
+
+    /// Like [`Parser::recover_to_sync`], but also stops before an open brace (the start of a
+    /// nested block) or a keyword that starts a new statement, so that a malformed statement
+    /// doesn't eat the start of the next one while skipping forward.
+    pub(super) fn recover_to_stmt_sync(&mut self) {
+        while !is_sync_token(&self.token.kind)
+            && !matches!(self.token.kind, TokenKind::OpenDelim(Delimiter::Brace))
+            && !is_stmt_start(&self.token)
+        {
+            self.bump();
+        }
+    }
+
+    /// Recovers from an unexpected token at `lo`: emits the diagnostic that `self.unexpected()`
+    /// would have returned, skips to a synchronizing token, and returns an `Err` placeholder
+    /// expression spanning the skipped region.
+    ///
+    /// This is what lets a caller keep building a (partial) AST instead of bailing out of the
+    /// whole parse on the first malformed expression.
+    pub(super) fn recover_expr(&mut self, lo: Span) -> Box<Expr> {
+        let guar = self.unexpected::<Box<Expr>>().unwrap_err().emit();
+        self.recover_to_sync();
+        let span = lo.to(self.prev_token.span);
+        Box::new(Expr { span, kind: ExprKind::Err(guar) })
+    }
+
+    /// Runs `f`, and if it fails, emits the diagnostic, skips to a synchronizing token or
+    /// statement-starting keyword via [`Parser::recover_to_stmt_sync`], and returns an `Err`
+    /// placeholder statement spanning the skipped region instead of propagating the failure.
+    ///
+    /// This is what lets one malformed statement inside a block (or control-flow body) leave the
+    /// rest of the enclosing parse intact instead of aborting it entirely.
+    pub(super) fn parse_stmt_or_recover(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> PResult<'a, Box<Stmt>>,
+    ) -> Box<Stmt> {
+        let lo = self.token.span;
+        match f(self) {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                let guar = err.emit();
+                self.recover_to_stmt_sync();
+                let span = lo.to(self.prev_token.span);
+                Box::new(Stmt { span, kind: StmtKind::Err(guar) })
+            }
+        }
+    }
+
+    /// Expects and consumes an opening delimiter, pushing it onto the open-delimiter stack so
+    /// that a later mismatched or missing closer can be reported against it.
+    pub(super) fn expect_open_delim(&mut self, delim: Delimiter) -> PResult<'a, ()> {
+        let span = self.token.span;
+        self.expect(&TokenKind::OpenDelim(delim))?;
+        self.open_delim(delim, span);
+        Ok(())
+    }
+
+    /// Pushes an already-consumed opening delimiter onto the open-delimiter stack.
+    ///
+    /// Used at call sites that consume the opener with [`Parser::eat`] rather than
+    /// [`Parser::expect`].
+    pub(super) fn open_delim(&mut self, delim: Delimiter, span: Span) {
+        self.open_delims.push((delim, span));
+    }
+
+    /// Expects a closing delimiter matching the most recently opened one.
+    ///
+    /// If the current token isn't it, emits a diagnostic pointing at both the unclosed opener
+    /// and the mismatch site, then recovers by treating the current position as the implied
+    /// close (without consuming it) so parsing of the surrounding code can continue.
+    pub(super) fn expect_close_delim(&mut self, delim: Delimiter) -> PResult<'a, ()> {
+        let opener = self.open_delims.pop();
+        if self.eat_noexpect(&TokenKind::CloseDelim(delim)) {
+            return Ok(());
+        }
+        let span = self.token.span;
+        let mut err = self.dcx().err("mismatched closing delimiter").span(span);
+        if let Some((_, open_span)) = opener {
+            err = err.span_label(open_span, "unclosed delimiter");
+        }
+        err.emit();
+        Ok(())
+    }
+}
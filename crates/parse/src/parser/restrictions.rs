@@ -0,0 +1,25 @@
+bitflags::bitflags! {
+    /// Restrictions applied to a sub-parse, toggled for the duration of a single parser call.
+    ///
+    /// Rather than threading extra boolean parameters through every `parse_*` signature, a flag
+    /// is stashed on the [`Parser`](super::Parser) and restored by the caller once the sub-parse
+    /// returns, mirroring rustc's `Restrictions`.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Restrictions: u8 {
+        /// Do not parse a trailing `{ ... }` as call options (`expr{value: 1}`).
+        ///
+        /// Set while parsing the header expression of a control-flow construct
+        /// (`for`/`while`/`if`/`try`) so that `parse_lhs_expr` treats a following `{` as the
+        /// start of the block rather than consuming it as call options.
+        const NO_CALL_OPTIONS = 1 << 0;
+
+        /// The expression being parsed is a genuine boolean condition, not just any header
+        /// expression.
+        ///
+        /// Set only for `if`/`while`/`do`-`while`'s condition and `for`'s condition clause (not
+        /// its init/update, nor `try`'s expression), so that `parse_expr_inner` can flag a bare
+        /// `=` as an almost-certain `==` typo without also firing on contexts where an assignment
+        /// is perfectly ordinary.
+        const IS_CONDITION = 1 << 1;
+    }
+}
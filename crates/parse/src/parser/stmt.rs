@@ -0,0 +1,200 @@
+use crate::{PResult, Parser, Restrictions};
+use sulk_ast::{ast::*, token::*};
+use sulk_interface::kw;
+
+impl<'a> Parser<'a> {
+    /// Parses a statement.
+    pub fn parse_stmt(&mut self) -> PResult<'a, Box<Stmt>> {
+        self.with_nesting_guard(Self::parse_stmt_inner)
+    }
+
+    fn parse_stmt_inner(&mut self) -> PResult<'a, Box<Stmt>> {
+        let lo = self.token.span;
+        let kind = if self.eat_keyword(kw::If) {
+            self.parse_if_stmt()?
+        } else if self.eat_keyword(kw::While) {
+            self.parse_while_stmt()?
+        } else if self.eat_keyword(kw::Do) {
+            self.parse_do_while_stmt()?
+        } else if self.eat_keyword(kw::For) {
+            self.parse_for_stmt()?
+        } else if self.eat_keyword(kw::Try) {
+            self.parse_try_stmt()?
+        } else if self.check(&TokenKind::OpenDelim(Delimiter::Brace)) {
+            StmtKind::Block(self.parse_block()?)
+        } else if self.eat(&TokenKind::Semi) {
+            StmtKind::Empty
+        } else {
+            let expr = self.parse_expr()?;
+            self.expect(&TokenKind::Semi)?;
+            StmtKind::Expr(expr)
+        };
+        let span = lo.to(self.prev_token.span);
+        Ok(Box::new(Stmt { span, kind }))
+    }
+
+    /// Parses a brace-delimited block of statements.
+    ///
+    /// A malformed statement doesn't abort the whole block: its diagnostic is emitted, the
+    /// parser skips to the next synchronizing token or statement-starting keyword, and an
+    /// `StmtKind::Err` placeholder takes its place, so one bad statement still leaves the caller
+    /// with the rest of the block instead of no parse at all.
+    pub(crate) fn parse_block(&mut self) -> PResult<'a, Vec<Box<Stmt>>> {
+        self.expect_open_delim(Delimiter::Brace)?;
+        let mut stmts = Vec::new();
+        while !self.check(&TokenKind::CloseDelim(Delimiter::Brace)) && !self.check(&TokenKind::Eof)
+        {
+            stmts.push(self.parse_stmt_or_recover(Self::parse_stmt));
+        }
+        self.expect_close_delim(Delimiter::Brace)?;
+        Ok(stmts)
+    }
+
+    /// Parses the parenthesized condition of a control-flow header, with
+    /// [`Restrictions::NO_CALL_OPTIONS`] set for its duration so that the header's trailing `{`
+    /// is never mistaken for call options (`expr{value: 1}`) on the condition, and
+    /// [`Restrictions::IS_CONDITION`] set so that a bare `=` here is flagged as a likely `==`
+    /// typo.
+    fn parse_header_cond(&mut self) -> PResult<'a, Box<Expr>> {
+        self.expect_open_delim(Delimiter::Parenthesis)?;
+        let cond = self.with_res(
+            Restrictions::NO_CALL_OPTIONS | Restrictions::IS_CONDITION,
+            Self::parse_expr,
+        )?;
+        self.expect_close_delim(Delimiter::Parenthesis)?;
+        Ok(cond)
+    }
+
+    fn parse_if_stmt(&mut self) -> PResult<'a, StmtKind> {
+        let cond = self.parse_header_cond()?;
+        let then = self.parse_stmt_or_recover(Self::parse_stmt);
+        let else_ = if self.eat_keyword(kw::Else) {
+            Some(self.parse_stmt_or_recover(Self::parse_stmt))
+        } else {
+            None
+        };
+        Ok(StmtKind::If(cond, then, else_))
+    }
+
+    fn parse_while_stmt(&mut self) -> PResult<'a, StmtKind> {
+        let cond = self.parse_header_cond()?;
+        let body = self.parse_stmt_or_recover(Self::parse_stmt);
+        Ok(StmtKind::While(cond, body))
+    }
+
+    fn parse_do_while_stmt(&mut self) -> PResult<'a, StmtKind> {
+        let body = self.parse_stmt_or_recover(Self::parse_stmt);
+        self.expect_keyword(kw::While)?;
+        let cond = self.parse_header_cond()?;
+        self.expect(&TokenKind::Semi)?;
+        Ok(StmtKind::DoWhile(body, cond))
+    }
+
+    fn parse_for_stmt(&mut self) -> PResult<'a, StmtKind> {
+        self.expect_open_delim(Delimiter::Parenthesis)?;
+        let init = if self.eat(&TokenKind::Semi) {
+            None
+        } else {
+            Some(self.with_res(Restrictions::NO_CALL_OPTIONS, |this| {
+                this.parse_stmt_or_recover(Self::parse_stmt)
+            }))
+        };
+        // The condition and update expressions sit inside the same parenthesized header as the
+        // init statement, so they need the same `{`-as-call-options suppression. Only the
+        // condition is a genuine boolean condition, so only it also gets `IS_CONDITION`; the
+        // update expression is an ordinary (often assignment) expression.
+        let cond = self.with_res(
+            Restrictions::NO_CALL_OPTIONS | Restrictions::IS_CONDITION,
+            |this| {
+                let cond =
+                    if this.check(&TokenKind::Semi) { None } else { Some(this.parse_expr()?) };
+                this.expect(&TokenKind::Semi)?;
+                Ok(cond)
+            },
+        )?;
+        let update = self.with_res(Restrictions::NO_CALL_OPTIONS, |this| {
+            if this.check(&TokenKind::CloseDelim(Delimiter::Parenthesis)) {
+                Ok(None)
+            } else {
+                this.parse_expr().map(Some)
+            }
+        })?;
+        self.expect_close_delim(Delimiter::Parenthesis)?;
+        let body = self.parse_stmt_or_recover(Self::parse_stmt);
+        Ok(StmtKind::For { init, cond, update, body })
+    }
+
+    fn parse_try_stmt(&mut self) -> PResult<'a, StmtKind> {
+        let expr = self.with_res(Restrictions::NO_CALL_OPTIONS, Self::parse_expr)?;
+        let block = self.parse_block()?;
+        let mut catch_clauses = Vec::new();
+        while self.eat_keyword(kw::Catch) {
+            catch_clauses.push(self.parse_block()?);
+        }
+        Ok(StmtKind::Try(expr, block, catch_clauses))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Lexer, ParseSess, Parser};
+    use sulk_ast::ast::{Stmt, StmtKind};
+    use sulk_interface::diagnostics::DiagCtxt;
+
+    fn parse_stmt(src: &str) -> (Box<Stmt>, ParseSess) {
+        let sess = ParseSess::new(DiagCtxt::default());
+        let tokens = Lexer::new(&sess, src).tokenize();
+        let stmt = Parser::new(&sess, tokens).parse_stmt().unwrap();
+        (stmt, sess)
+    }
+
+    #[test]
+    fn assignment_in_if_condition_is_flagged() {
+        // A bare `=` in a control-flow condition is almost always a typo for `==`; this is only
+        // reachable once `if`'s condition is actually parsed with `IS_CONDITION` set.
+        let (_, sess) = parse_stmt("if (a = b) {}");
+        assert!(sess.dcx.has_errors().is_some());
+    }
+
+    #[test]
+    fn call_options_after_while_condition_starts_the_body() {
+        // The `{` after the condition's closing `)` starts the loop body; it is not parsed as
+        // `cond{...}` call options, so the statement parses without errors.
+        let (_, sess) = parse_stmt("while (cond) {}");
+        assert!(sess.dcx.has_errors().is_none());
+    }
+
+    #[test]
+    fn assignment_in_for_update_is_not_flagged() {
+        // `NO_CALL_OPTIONS` is set for the whole `for (...)` header, but only the condition
+        // clause is a genuine boolean condition: a bare `=` in the update expression is an
+        // ordinary assignment and must not be flagged as an `==` typo.
+        let (_, sess) = parse_stmt("for (i = 0; i < 10; i = i + 1) {}");
+        assert!(sess.dcx.has_errors().is_none());
+    }
+
+    #[test]
+    fn assignment_in_for_condition_is_flagged() {
+        let (_, sess) = parse_stmt("for (i = 0; i = 10; i = i + 1) {}");
+        assert!(sess.dcx.has_errors().is_some());
+    }
+
+    #[test]
+    fn malformed_statement_recovers_instead_of_aborting_the_block() {
+        // The first statement is malformed (missing its terminating `;`), but the block should
+        // still contain all three statements -- an `Err` placeholder, the empty statement it
+        // resyncs on, and the valid statement after it -- instead of the whole parse aborting.
+        let (stmt, sess) = parse_stmt("{ a b; c; }");
+        let StmtKind::Block(stmts) = &stmt.kind else { panic!("expected a block") };
+        assert_eq!(stmts.len(), 3);
+        assert!(
+            matches!(stmts[0].kind, StmtKind::Err(_)),
+            "first statement should recover to `Err`"
+        );
+        assert!(
+            matches!(stmts[2].kind, StmtKind::Expr(_)),
+            "parsing should continue after recovery"
+        );
+        assert!(sess.dcx.has_errors().is_some());
+    }
+}
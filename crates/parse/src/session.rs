@@ -0,0 +1,37 @@
+use std::cell::Cell;
+use sulk_interface::diagnostics::DiagCtxt;
+
+/// Default value for [`ParseSess::recursion_limit`].
+pub const DEFAULT_RECURSION_LIMIT: usize = 256;
+
+/// Parser session: state shared across an entire parse, independent of any single [`Parser`](crate::Parser).
+pub struct ParseSess {
+    /// The diagnostics context used to emit errors and warnings.
+    pub dcx: DiagCtxt,
+
+    /// Maximum allowed expression/type nesting depth before the parser bails out with a fatal
+    /// "expression nesting too deep" diagnostic.
+    ///
+    /// Guards against stack overflow on pathologically nested input, e.g. thousands of nested
+    /// parentheses. Defaults to [`DEFAULT_RECURSION_LIMIT`]; embedders parsing untrusted input
+    /// can tune it with [`ParseSess::set_recursion_limit`].
+    recursion_limit: Cell<usize>,
+}
+
+impl ParseSess {
+    /// Creates a new parser session with the default recursion limit.
+    pub fn new(dcx: DiagCtxt) -> Self {
+        Self { dcx, recursion_limit: Cell::new(DEFAULT_RECURSION_LIMIT) }
+    }
+
+    /// Returns the current expression/type nesting limit.
+    pub fn recursion_limit(&self) -> usize {
+        self.recursion_limit.get()
+    }
+
+    /// Overrides the expression/type nesting limit used to guard against pathologically nested
+    /// input.
+    pub fn set_recursion_limit(&self, limit: usize) {
+        self.recursion_limit.set(limit);
+    }
+}